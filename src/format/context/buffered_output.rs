@@ -0,0 +1,78 @@
+use super::destructor::Mode;
+use super::Output;
+use ffi;
+use std::ffi::c_void;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use Error;
+
+/// An output format context muxing into FFmpeg's own growable dynamic
+/// buffer (`avio_open_dyn_buf`) instead of a caller-supplied seekable sink.
+///
+/// Several container muxers (fragmented MP4, MPEG-TS) happily write
+/// forward-only, so this avoids having to back a `Cursor<Vec<u8>>` through
+/// [`super::StreamIo`] just to get a `Seek` impl. Internally the new type
+/// wraps a regular `context::Output`, so add streams and write the
+/// header/packets exactly as you would on a regular `context::Output`,
+/// then call [`BufferedOutput::close`] to reclaim the accumulated bytes.
+pub struct BufferedOutput {
+    output: Output<'static>,
+}
+
+impl BufferedOutput {
+    pub(crate) unsafe fn wrap(ptr: *mut ffi::AVFormatContext) -> Self {
+        BufferedOutput {
+            output: unsafe { Output::wrap_with_mode(ptr, Mode::OutputDynBuf) },
+        }
+    }
+
+    /// Writes the trailer, retrieves the bytes FFmpeg accumulated in the
+    /// dynamic buffer, and frees the format context.
+    pub fn close(mut self) -> Result<Vec<u8>, Error> {
+        unsafe {
+            let ptr = self.output.as_mut_ptr();
+
+            match ffi::av_write_trailer(ptr) {
+                e if e < 0 => return Err(Error::from(e)),
+                _ => {}
+            }
+
+            let mut buf: *mut u8 = ptr::null_mut();
+            let size = ffi::avio_close_dyn_buf((*ptr).pb, &mut buf);
+            (*ptr).pb = ptr::null_mut();
+
+            let data = if buf.is_null() || size <= 0 {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(buf, size as usize).to_vec()
+            };
+            ffi::av_free(buf as *mut c_void);
+
+            // `self.output` (and its `Destructor`) drops here: `pb` is now
+            // null, so the `OutputDynBuf` arm just frees the context.
+            Ok(data)
+        }
+    }
+}
+
+impl Deref for BufferedOutput {
+    type Target = Output<'static>;
+
+    fn deref(&self) -> &Output<'static> {
+        &self.output
+    }
+}
+
+impl DerefMut for BufferedOutput {
+    fn deref_mut(&mut self) -> &mut Output<'static> {
+        &mut self.output
+    }
+}
+
+impl std::fmt::Debug for BufferedOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedOutput")
+            .field("ptr", &self.output.as_ptr())
+            .finish()
+    }
+}
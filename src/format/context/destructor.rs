@@ -2,25 +2,54 @@ use super::StreamIo;
 use ffi::*;
 
 #[derive(Debug)]
-pub enum Mode {
+pub enum Mode<'a> {
     Input,
     Output,
-    InputCustomIo(StreamIo),
-    OutputCustomIo(StreamIo),
+    InputCustomIo(StreamIo<'a>),
+    OutputCustomIo(StreamIo<'a>),
+    /// Output backed by FFmpeg's own dynamic buffer (`avio_open_dyn_buf`)
+    /// rather than a URL-backed `pb`. Freed with `avio_close_dyn_buf`
+    /// instead of `avio_close`.
+    OutputDynBuf,
 }
 
-pub struct Destructor {
+pub struct Destructor<'a> {
     ptr: *mut AVFormatContext,
-    mode: Mode,
+    mode: Mode<'a>,
 }
 
-impl Destructor {
-    pub unsafe fn new(ptr: *mut AVFormatContext, mode: Mode) -> Self {
+impl<'a> Destructor<'a> {
+    pub unsafe fn new(ptr: *mut AVFormatContext, mode: Mode<'a>) -> Self {
         Destructor { ptr, mode }
     }
+
+    /// Closes the underlying `AVFormatContext` exactly like `Drop` would,
+    /// but for a custom-I/O mode returns the wrapped `StreamIo` instead of
+    /// dropping it, so the owning `Input`/`Output` can hand the original
+    /// Rust stream back to the caller. Returns `None` for any mode that
+    /// owns no reclaimable stream, in which case `self` is left untouched
+    /// and closed normally once it is dropped.
+    pub(crate) fn reclaim(mut self) -> Option<StreamIo<'a>> {
+        match std::mem::replace(&mut self.mode, Mode::Input) {
+            Mode::InputCustomIo(io) => {
+                unsafe { avformat_close_input(&mut self.ptr) };
+                std::mem::forget(self);
+                Some(io)
+            }
+            Mode::OutputCustomIo(io) => {
+                unsafe { avformat_free_context(self.ptr) };
+                std::mem::forget(self);
+                Some(io)
+            }
+            mode => {
+                self.mode = mode;
+                None
+            }
+        }
+    }
 }
 
-impl Drop for Destructor {
+impl<'a> Drop for Destructor<'a> {
     fn drop(&mut self) {
         unsafe {
             match self.mode {
@@ -38,6 +67,22 @@ impl Drop for Destructor {
                     avio_close((*self.ptr).pb);
                     avformat_free_context(self.ptr);
                 }
+
+                Mode::OutputDynBuf => {
+                    // `BufferedOutput::close` already drains the dyn buffer
+                    // and nulls `pb`; `pb` is still set only if the caller
+                    // drops the context without calling `close`, or if
+                    // `close` failed to write the trailer, in which case we
+                    // just discard the accumulated bytes.
+                    if !(*self.ptr).pb.is_null() {
+                        let mut buf: *mut u8 = std::ptr::null_mut();
+                        avio_close_dyn_buf((*self.ptr).pb, &mut buf);
+                        if !buf.is_null() {
+                            av_free(buf as *mut std::ffi::c_void);
+                        }
+                    }
+                    avformat_free_context(self.ptr);
+                }
             }
         }
     }
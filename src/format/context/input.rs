@@ -0,0 +1,59 @@
+use super::destructor::{Destructor, Mode};
+use super::StreamIo;
+use ffi::*;
+
+/// A demuxer's format context: the input side of `Context`.
+pub struct Input<'a> {
+    ptr: *mut AVFormatContext,
+    destructor: Destructor<'a>,
+}
+
+impl<'a> Input<'a> {
+    pub unsafe fn wrap(ptr: *mut AVFormatContext) -> Self {
+        Input {
+            ptr,
+            destructor: unsafe { Destructor::new(ptr, Mode::Input) },
+        }
+    }
+
+    pub unsafe fn wrap_with_custom_io(ptr: *mut AVFormatContext, custom_io: StreamIo<'a>) -> Self {
+        Input {
+            ptr,
+            destructor: unsafe { Destructor::new(ptr, Mode::InputCustomIo(custom_io)) },
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const AVFormatContext {
+        self.ptr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut AVFormatContext {
+        self.ptr
+    }
+
+    /// Closes the input and, if it was opened via [`super::super::input_from_stream`],
+    /// hands the original custom-I/O stream back to the caller — the other
+    /// half of the "demux from a `Cursor<Vec<u8>>`" workflow.
+    ///
+    /// # Safety
+    /// `T` must be exactly the type originally passed to the `StreamIo`
+    /// constructor backing this `Input`.
+    ///
+    /// # Panics
+    /// Panics if this `Input` was not opened with a custom-I/O stream.
+    pub unsafe fn into_inner<T>(self) -> T {
+        let custom_io = self
+            .destructor
+            .reclaim()
+            .expect("Input::into_inner called on an Input without custom I/O");
+        unsafe { custom_io.into_inner() }
+    }
+}
+
+unsafe impl<'a> Send for Input<'a> {}
+
+impl<'a> std::fmt::Debug for Input<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Input").field("ptr", &self.ptr).finish()
+    }
+}
@@ -0,0 +1,19 @@
+pub(crate) mod destructor;
+pub use self::destructor::{Destructor, Mode};
+
+mod stream_io;
+pub use self::stream_io::{StreamIo, StreamIoBuilder};
+
+mod buffered_output;
+pub use self::buffered_output::BufferedOutput;
+
+mod input;
+pub use self::input::Input;
+
+mod output;
+pub use self::output::Output;
+
+pub enum Context<'a> {
+    Input(Input<'a>),
+    Output(Output<'a>),
+}
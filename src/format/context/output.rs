@@ -0,0 +1,62 @@
+use super::destructor::{Destructor, Mode};
+use super::StreamIo;
+use ffi::*;
+
+/// A muxer's format context: the output side of `Context`.
+pub struct Output<'a> {
+    ptr: *mut AVFormatContext,
+    destructor: Destructor<'a>,
+}
+
+impl<'a> Output<'a> {
+    pub(crate) unsafe fn wrap_with_mode(ptr: *mut AVFormatContext, mode: Mode<'a>) -> Self {
+        Output {
+            ptr,
+            destructor: unsafe { Destructor::new(ptr, mode) },
+        }
+    }
+
+    pub unsafe fn wrap(ptr: *mut AVFormatContext) -> Self {
+        unsafe { Output::wrap_with_mode(ptr, Mode::Output) }
+    }
+
+    pub unsafe fn wrap_with_custom_io(ptr: *mut AVFormatContext, custom_io: StreamIo<'a>) -> Self {
+        unsafe { Output::wrap_with_mode(ptr, Mode::OutputCustomIo(custom_io)) }
+    }
+
+    pub fn as_ptr(&self) -> *const AVFormatContext {
+        self.ptr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut AVFormatContext {
+        self.ptr
+    }
+
+    /// Closes the output and, if it was opened via [`super::super::output_to_stream`],
+    /// hands the original custom-I/O stream back to the caller — the other
+    /// half of the "mux into a `Cursor<Vec<u8>>`, then read out the bytes"
+    /// workflow: once the muxer is finished with this `Output`, call
+    /// `into_inner` to get the stream back.
+    ///
+    /// # Safety
+    /// `T` must be exactly the type originally passed to the `StreamIo`
+    /// constructor backing this `Output`.
+    ///
+    /// # Panics
+    /// Panics if this `Output` was not opened with a custom-I/O stream.
+    pub unsafe fn into_inner<T>(self) -> T {
+        let custom_io = self
+            .destructor
+            .reclaim()
+            .expect("Output::into_inner called on an Output without custom I/O");
+        unsafe { custom_io.into_inner() }
+    }
+}
+
+unsafe impl<'a> Send for Output<'a> {}
+
+impl<'a> std::fmt::Debug for Output<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Output").field("ptr", &self.ptr).finish()
+    }
+}
@@ -1,6 +1,8 @@
 use ffi;
 use std::ffi::{c_int, c_void};
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::ptr;
 use Error;
 
 /// Default internal I/O buffer size used by the underlying `AVIOContext`.
@@ -26,15 +28,22 @@ const BUFFER_SIZE: usize = 16384;
 /// - `StreamIo` **owns** both the C `AVIOContext` and the boxed Rust stream.
 /// - Dropping `StreamIo` frees the internal buffer, the `AVIOContext`,
 ///   and the boxed stream in the correct order.
+/// - The `'a` parameter ties `StreamIo` to the stream it wraps, so a borrowed
+///   stream such as `&'a mut File` can back it too: the borrow checker will
+///   refuse to let the `StreamIo` (and anything built on top of it, like a
+///   `context::Input`/`Output`) outlive the borrow.
 /// - You must ensure the `AVIOContext*` returned by [`StreamIo::as_mut_ptr`]
 ///   does not outlive the `StreamIo` that created it.
 ///
 /// # Thread-safety
 ///
-/// The underlying Rust stream is not synchronized; callbacks are invoked
-/// by FFmpeg on the calling thread. Do not share the same `StreamIo`
-/// across threads unless the wrapped stream itself is thread-safe and FFmpeg
-/// will not call the callbacks concurrently.
+/// `StreamIo` is `Send`: every constructor requires the wrapped stream to be
+/// `Send`, so it is always sound to move a `StreamIo` (and a `Context` built
+/// on top of it) to another thread. It is not `Sync` — FFmpeg will only ever
+/// invoke the read/write/seek callbacks from whichever single thread
+/// currently drives the context, so the underlying stream does not need its
+/// own internal synchronization, but it also must not be accessed from two
+/// threads at once.
 ///
 /// # EOF
 ///
@@ -46,37 +55,52 @@ const BUFFER_SIZE: usize = 16384;
 ///   You must make sure this pointer does not outlive the `StreamIo` instance.
 ///
 /// [`AVIOContext`]: https://ffmpeg.org/doxygen/trunk/structAVIOContext.html
-pub struct StreamIo {
+pub struct StreamIo<'a> {
     ptr: *mut ffi::AVIOContext,
     drop_opaque: fn(*mut c_void),
+    _stream: PhantomData<&'a mut ()>,
 }
-impl StreamIo {
-    pub fn from_read<T: Read>(stream: T) -> Result<Self, Error> {
+impl<'a> StreamIo<'a> {
+    pub fn from_read<T: Read + Send + 'a>(stream: T) -> Result<Self, Error> {
         Self::new_impl(stream, Some(read::<T>), None, None)
     }
-    pub fn from_read_seek<T: Read + Seek>(stream: T) -> Result<Self, Error> {
+    pub fn from_read_seek<T: Read + Seek + Send + 'a>(stream: T) -> Result<Self, Error> {
         Self::new_impl(stream, Some(read::<T>), None, Some(seek::<T>))
     }
-    pub fn from_read_write_seek<T: Read + Write + Seek>(stream: T) -> Result<Self, Error> {
+    pub fn from_read_write_seek<T: Read + Write + Seek + Send + 'a>(
+        stream: T,
+    ) -> Result<Self, Error> {
         Self::new_impl(stream, Some(read::<T>), Some(write::<T>), Some(seek::<T>))
     }
-    pub fn from_read_write<T: Read + Write>(stream: T) -> Result<Self, Error> {
+    pub fn from_read_write<T: Read + Write + Send + 'a>(stream: T) -> Result<Self, Error> {
         Self::new_impl(stream, Some(read::<T>), Some(write::<T>), None)
     }
-    pub fn from_write<T: Write>(stream: T) -> Result<Self, Error> {
+    pub fn from_write<T: Write + Send + 'a>(stream: T) -> Result<Self, Error> {
         Self::new_impl(stream, None, Some(write::<T>), None)
     }
-    pub fn from_write_seek<T: Write + Seek>(stream: T) -> Result<Self, Error> {
+    pub fn from_write_seek<T: Write + Seek + Send + 'a>(stream: T) -> Result<Self, Error> {
         Self::new_impl(stream, None, Some(write::<T>), Some(seek::<T>))
     }
 
-    fn new_impl<T>(
+    fn new_impl<T: Send + 'a>(
         stream: T,
         r: Option<unsafe extern "C" fn(*mut c_void, *mut u8, c_int) -> c_int>,
         w: Option<unsafe extern "C" fn(*mut c_void, *const u8, c_int) -> c_int>,
         s: Option<unsafe extern "C" fn(*mut c_void, i64, c_int) -> i64>,
     ) -> Result<Self, Error> {
-        let buffer = unsafe { ffi::av_malloc(BUFFER_SIZE) };
+        let write = w.is_some();
+        Self::new_impl_with(stream, r, w, s, BUFFER_SIZE, write)
+    }
+
+    fn new_impl_with<T: Send + 'a>(
+        stream: T,
+        r: Option<unsafe extern "C" fn(*mut c_void, *mut u8, c_int) -> c_int>,
+        w: Option<unsafe extern "C" fn(*mut c_void, *const u8, c_int) -> c_int>,
+        s: Option<unsafe extern "C" fn(*mut c_void, i64, c_int) -> i64>,
+        buffer_size: usize,
+        write: bool,
+    ) -> Result<Self, Error> {
+        let buffer = unsafe { ffi::av_malloc(buffer_size) };
         if buffer.is_null() {
             return Err(Error::Other { errno: ffi::ENOMEM });
         }
@@ -84,8 +108,8 @@ impl StreamIo {
         let ptr = unsafe {
             ffi::avio_alloc_context(
                 buffer as *mut _,
-                BUFFER_SIZE as _,
-                w.is_some() as _,
+                buffer_size as _,
+                write as _,
                 stream_box_ptr,
                 r,
                 w,
@@ -105,6 +129,44 @@ impl StreamIo {
         Ok(Self {
             ptr,
             drop_opaque: drop_box::<T>,
+            _stream: PhantomData,
+        })
+    }
+
+    /// Returns a builder for configuring the internal `AVIOContext` buffer
+    /// size, whether the write flag is set, and whether the seek callback is
+    /// actually wired up, before constructing a `StreamIo` for `T`.
+    pub fn builder<T>() -> StreamIoBuilder<T> {
+        StreamIoBuilder::new()
+    }
+
+    /// Flushes any data buffered by the `AVIOContext` out to the underlying
+    /// `Write` stream. Dropping `StreamIo` does not flush: it frees the
+    /// buffer along with whatever is still in it.
+    pub fn flush(&mut self) {
+        unsafe {
+            ffi::avio_flush(self.ptr);
+        }
+    }
+
+    /// Wraps a pull-based chunk source — e.g. the receiving end of a channel
+    /// fed by a live network task — as a read-only `StreamIo`, for demuxing
+    /// input that arrives as discrete chunks rather than through a `Read`.
+    ///
+    /// `recv` is called to pull the next chunk whenever the current one is
+    /// exhausted; returning `None` signals the source is closed and is
+    /// translated to `AVERROR_EOF`. Each read callback copies at most
+    /// `min(buf_size, remaining)` bytes from the current chunk, so a chunk
+    /// larger than the buffer FFmpeg offers is correctly split across
+    /// multiple calls instead of being dropped or overrun.
+    pub fn from_chunk_source<F>(recv: F) -> Result<Self, Error>
+    where
+        F: FnMut() -> Option<Vec<u8>> + Send + 'a,
+    {
+        Self::from_read(ChunkSource {
+            recv,
+            chunk: Vec::new(),
+            pos: 0,
         })
     }
 
@@ -116,9 +178,41 @@ impl StreamIo {
     pub fn as_mut_ptr(&mut self) -> *mut ffi::AVIOContext {
         self.ptr
     }
+
+    /// Frees the `AVIOContext` and hands the wrapped Rust stream back to the
+    /// caller, instead of dropping it.
+    ///
+    /// This only works while the `StreamIo` is still held directly — once it
+    /// has been passed to [`super::super::input_from_stream`] or
+    /// [`super::super::output_to_stream`], the `Context` built on top of it
+    /// owns it and this method is no longer reachable. For the common "mux
+    /// into a `Cursor<Vec<u8>>`, then read out the bytes" workflow, call
+    /// [`super::Input::into_inner`] or [`super::Output::into_inner`] on the
+    /// `Context` instead, once the muxer/demuxer is finished with it; those
+    /// delegate to this method internally.
+    ///
+    /// # Safety
+    /// `T` must be exactly the type originally passed to the constructor that
+    /// produced this `StreamIo` (e.g. the `T` given to [`StreamIo::from_read`]
+    /// or [`StreamIo::from_write_seek`]). Supplying any other type reinterprets
+    /// the boxed stream as that type, which is undefined behavior.
+    pub unsafe fn into_inner<T>(mut self) -> T {
+        unsafe {
+            let opaque = (*self.ptr).opaque;
+            let stream = *Box::from_raw(opaque as *mut T);
+            ffi::av_freep(&raw mut (*self.ptr).buffer as *mut c_void);
+            ffi::avio_context_free(&mut self.ptr);
+            self.ptr = ptr::null_mut();
+            stream
+        }
+    }
 }
 
-impl Drop for StreamIo {
+// Sound because every constructor bounds its stream type on `Send`, so the
+// boxed stream an instance wraps is always `Send` by construction.
+unsafe impl<'a> Send for StreamIo<'a> {}
+
+impl<'a> Drop for StreamIo<'a> {
     fn drop(&mut self) {
         if !self.ptr.is_null() {
             unsafe {
@@ -131,12 +225,114 @@ impl Drop for StreamIo {
     }
 }
 
-impl std::fmt::Debug for StreamIo {
+impl<'a> std::fmt::Debug for StreamIo<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StreamIo").field("ptr", &self.ptr).finish()
     }
 }
 
+/// Configures a [`StreamIo`] before construction: the internal `AVIOContext`
+/// buffer size, the write flag, and whether the seek callback is actually
+/// wired up. Obtained via [`StreamIo::builder`].
+pub struct StreamIoBuilder<T> {
+    buffer_size: usize,
+    write: Option<bool>,
+    seekable: bool,
+    _stream: PhantomData<fn() -> T>,
+}
+
+impl<T> StreamIoBuilder<T> {
+    fn new() -> Self {
+        StreamIoBuilder {
+            buffer_size: BUFFER_SIZE,
+            write: None,
+            seekable: true,
+            _stream: PhantomData,
+        }
+    }
+
+    /// Overrides the default 16 KiB internal `AVIOContext` buffer size.
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Overrides the write flag passed to `avio_alloc_context`, instead of
+    /// inferring it from which callbacks are present. Useful for a
+    /// read-only protocol backed by a `Read + Write` type where the write
+    /// half should not be advertised to FFmpeg.
+    pub fn write(mut self, write: bool) -> Self {
+        self.write = Some(write);
+        self
+    }
+
+    /// Controls whether the seek callback is registered for `build_*seek`
+    /// methods, even though `T: Seek`. Pass `false` to force FFmpeg to treat
+    /// the stream as forward-only.
+    pub fn seekable(mut self, seekable: bool) -> Self {
+        self.seekable = seekable;
+        self
+    }
+
+    pub fn build_read<'a>(self, stream: T) -> Result<StreamIo<'a>, Error>
+    where
+        T: Read + Send + 'a,
+    {
+        let write = self.write.unwrap_or(false);
+        StreamIo::new_impl_with(stream, Some(read::<T>), None, None, self.buffer_size, write)
+    }
+
+    pub fn build_write<'a>(self, stream: T) -> Result<StreamIo<'a>, Error>
+    where
+        T: Write + Send + 'a,
+    {
+        let write = self.write.unwrap_or(true);
+        StreamIo::new_impl_with(stream, None, Some(write::<T>), None, self.buffer_size, write)
+    }
+
+    pub fn build_read_seek<'a>(self, stream: T) -> Result<StreamIo<'a>, Error>
+    where
+        T: Read + Seek + Send + 'a,
+    {
+        let write = self.write.unwrap_or(false);
+        let s = self.seekable.then_some(seek::<T>);
+        StreamIo::new_impl_with(stream, Some(read::<T>), None, s, self.buffer_size, write)
+    }
+
+    pub fn build_write_seek<'a>(self, stream: T) -> Result<StreamIo<'a>, Error>
+    where
+        T: Write + Seek + Send + 'a,
+    {
+        let write = self.write.unwrap_or(true);
+        let s = self.seekable.then_some(seek::<T>);
+        StreamIo::new_impl_with(stream, None, Some(write::<T>), s, self.buffer_size, write)
+    }
+
+    pub fn build_read_write<'a>(self, stream: T) -> Result<StreamIo<'a>, Error>
+    where
+        T: Read + Write + Send + 'a,
+    {
+        let write = self.write.unwrap_or(true);
+        StreamIo::new_impl_with(
+            stream,
+            Some(read::<T>),
+            Some(write::<T>),
+            None,
+            self.buffer_size,
+            write,
+        )
+    }
+
+    pub fn build_read_write_seek<'a>(self, stream: T) -> Result<StreamIo<'a>, Error>
+    where
+        T: Read + Write + Seek + Send + 'a,
+    {
+        let write = self.write.unwrap_or(true);
+        let s = self.seekable.then_some(seek::<T>);
+        StreamIo::new_impl_with(stream, Some(read::<T>), Some(write::<T>), s, self.buffer_size, write)
+    }
+}
+
 unsafe extern "C" fn read<T: Read>(opaque: *mut c_void, buf: *mut u8, buf_size: c_int) -> c_int {
     let buf = unsafe { std::slice::from_raw_parts_mut(buf, buf_size as usize) };
     let stream = unsafe { &mut *(opaque as *mut T) };
@@ -196,3 +392,42 @@ fn map_io_error(e: std::io::Error) -> i32 {
         _ => ffi::AVERROR(ffi::EIO),
     }
 }
+
+/// Adapts a pull-based chunk source (`F: FnMut() -> Option<Vec<u8>>`) into a
+/// `Read`, retaining the unconsumed tail of the current chunk between calls.
+/// Backs [`StreamIo::from_chunk_source`].
+struct ChunkSource<F> {
+    recv: F,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl<F: FnMut() -> Option<Vec<u8>>> Read for ChunkSource<F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // A zero-length `buf` can only be answered with `Ok(0)`; return it
+        // without pulling from the chunk source, so any buffered chunk is
+        // kept for the next call.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            let remaining = self.chunk.len() - self.pos;
+            if remaining > 0 {
+                let n = remaining.min(buf.len());
+                buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+                self.pos += n;
+                return Ok(n);
+            }
+
+            match (self.recv)() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                    // An empty chunk is not EOF; pull again.
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
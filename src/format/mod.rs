@@ -63,7 +63,7 @@ fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> CString {
 }
 
 // NOTE: this will be better with specialization or anonymous return types
-pub fn open<P: AsRef<Path> + ?Sized>(path: &P, format: &Format) -> Result<Context, Error> {
+pub fn open<P: AsRef<Path> + ?Sized>(path: &P, format: &Format) -> Result<Context<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -104,7 +104,7 @@ pub fn open_with<P: AsRef<Path> + ?Sized>(
     path: &P,
     format: &Format,
     options: Dictionary,
-) -> Result<Context, Error> {
+) -> Result<Context<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -148,7 +148,7 @@ pub fn open_with<P: AsRef<Path> + ?Sized>(
     }
 }
 
-pub fn input<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Input, Error> {
+pub fn input<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Input<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -170,7 +170,7 @@ pub fn input<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Input, Error>
 pub fn input_with_dictionary<P: AsRef<Path> + ?Sized>(
     path: &P,
     options: Dictionary,
-) -> Result<context::Input, Error> {
+) -> Result<context::Input<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -196,7 +196,7 @@ pub fn input_with_dictionary<P: AsRef<Path> + ?Sized>(
 pub fn input_with_interrupt<P: AsRef<Path> + ?Sized, F>(
     path: &P,
     closure: F,
-) -> Result<context::Input, Error>
+) -> Result<context::Input<'static>, Error>
 where
     F: FnMut() -> bool,
 {
@@ -224,11 +224,11 @@ where
 ///
 /// You can optionally include a filename to help with format detection,
 /// and a dictionary of options to configure the format context.
-pub fn input_from_stream(
-    mut custom_io: context::StreamIo,
+pub fn input_from_stream<'a>(
+    mut custom_io: context::StreamIo<'a>,
     filename: Option<&str>,
     options: Option<Dictionary>,
-) -> Result<context::Input, Error> {
+) -> Result<context::Input<'a>, Error> {
     unsafe {
         let mut ps = avformat_alloc_context();
         (*ps).pb = custom_io.as_mut_ptr();
@@ -259,7 +259,7 @@ pub fn input_from_stream(
     }
 }
 
-pub fn output<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Output, Error> {
+pub fn output<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Output<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -278,7 +278,7 @@ pub fn output<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Output, Erro
 pub fn output_with<P: AsRef<Path> + ?Sized>(
     path: &P,
     options: Dictionary,
-) -> Result<context::Output, Error> {
+) -> Result<context::Output<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -310,7 +310,7 @@ pub fn output_with<P: AsRef<Path> + ?Sized>(
 pub fn output_as<P: AsRef<Path> + ?Sized>(
     path: &P,
     format: &str,
-) -> Result<context::Output, Error> {
+) -> Result<context::Output<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -336,7 +336,7 @@ pub fn output_as_with<P: AsRef<Path> + ?Sized>(
     path: &P,
     format: &str,
     options: Dictionary,
-) -> Result<context::Output, Error> {
+) -> Result<context::Output<'static>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
         let path = from_path(path);
@@ -376,11 +376,11 @@ pub fn output_as_with<P: AsRef<Path> + ?Sized>(
 ///
 /// You can optionally include a filename to infer the output format from that,
 /// or specify the format explicitly.
-pub fn output_to_stream(
-    mut custom_io: context::StreamIo,
+pub fn output_to_stream<'a>(
+    mut custom_io: context::StreamIo<'a>,
     filename: Option<&str>,
     format: Option<&str>,
-) -> Result<context::Output, Error> {
+) -> Result<context::Output<'a>, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
 
@@ -401,3 +401,38 @@ pub fn output_to_stream(
         }
     }
 }
+
+/// Creates an output context that muxes into an FFmpeg-managed growable
+/// memory buffer instead of a seekable sink.
+///
+/// Unlike [`output_to_stream`], this does not require `Seek`: several
+/// container muxers (fragmented MP4, MPEG-TS) are happy writing
+/// forward-only. Write streams/header/packets on the returned
+/// `BufferedOutput` as usual, then call [`context::BufferedOutput::close`]
+/// to retrieve the muxed bytes as an owned `Vec<u8>`.
+pub fn output_to_buffer(
+    filename: Option<&str>,
+    format: Option<&str>,
+) -> Result<context::BufferedOutput, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+
+        let filename = filename.map(|f| CString::new(f).unwrap());
+        let filename_ptr = filename.as_ref().map_or(ptr::null(), |f| f.as_ptr());
+
+        let format = format.map(|f| CString::new(f).unwrap());
+        let format_ptr = format.as_ref().map_or(ptr::null(), |f| f.as_ptr());
+
+        match avformat_alloc_output_context2(&mut ps, ptr::null_mut(), format_ptr, filename_ptr) {
+            0 => match avio_open_dyn_buf(&mut (*ps).pb) {
+                0 => Ok(context::BufferedOutput::wrap(ps)),
+                e => {
+                    avformat_free_context(ps);
+                    Err(Error::from(e))
+                }
+            },
+
+            e => Err(Error::from(e)),
+        }
+    }
+}